@@ -2,10 +2,13 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use tauri::Manager;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,12 +17,38 @@ struct FileInfo {
     path: String,
     size: u64,
     extension: String,
+    // Last-modified time as seconds since the Unix epoch (0 when unknown).
+    modified_date: u64,
+}
+
+// A path we could not categorize, kept around so the UI can show the user
+// exactly what was skipped instead of us silently dropping it on the floor.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind")]
+enum BadMatch {
+    // The OS refused us (permission denied, not found, ...); carries the raw errno.
+    OsError { path: String, code: i32 },
+    // Something that is neither a regular file nor a directory (socket, fifo, any
+    // symlink: links are not followed).
+    BadType { path: String },
+    // Readable as an entry but its name/metadata could not be decoded.
+    Unreadable { path: String },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct FolderAnalysis {
     total_files: usize,
     categories: HashMap<String, Vec<FileInfo>>,
+    bad_paths: Vec<BadMatch>,
+}
+
+// Result of scanning a single directory: child directories to keep walking,
+// the files we kept, and anything that went wrong while reading it.
+#[derive(Default)]
+struct DirScan {
+    subdirs: Vec<PathBuf>,
+    files: Vec<FileInfo>,
+    bad: Vec<BadMatch>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,105 +57,616 @@ struct FileMove {
     to: String,
 }
 
-// Smart file categorization logic
-fn categorize_file(file_info: &FileInfo) -> String {
-    let file_name = file_info.name.to_lowercase();
-    let extension = file_info.extension.to_lowercase();
-    
-    // Check for sensitive files first
-    if is_sensitive_file(&file_name) {
-        return "Sensitive".to_string();
+// Snapshot of long-running work, emitted to the frontend so it can draw a
+// progress bar. `current_stage`/`max_stage` track coarse phases (scanning,
+// moving, ...); `files_checked` ticks up as we go.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ProgressData {
+    current_stage: u8,
+    max_stage: u8,
+    files_checked: usize,
+}
+
+// Shared cancellation flag so a user can abort a scan mid-flight. Checked by
+// `analyze_folder` between directory batches; aborting returns a partial
+// analysis. `organize_files` and the duplicate pass do not check it.
+#[derive(Default)]
+struct ScanControl {
+    cancel: AtomicBool,
+}
+
+// Don't spam the UI thread: emit progress at most this often.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// A single categorization rule, the data form of what used to be hardcoded in
+// `categorize_file`. A file matches a rule when its extension is in `extensions`
+// (or `extensions` is empty = any) AND its lowercased name contains one of
+// `keywords` or matches one of `patterns` (or both lists are empty = any).
+// Rules are tried from highest `priority` down, so "Sensitive" still wins.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CategoryRule {
+    category: String,
+    priority: i32,
+    #[serde(default)]
+    extensions: Vec<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    patterns: Vec<String>,
+}
+
+// The on-disk rule set. Serialized to the app config dir as `rules.json`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct RulesConfig {
+    rules: Vec<CategoryRule>,
+}
+
+// The built-in defaults, used when no config file is present. These mirror the
+// original hardcoded categories; the one intentional change is the Personal
+// Photos date heuristic, which was tightened to a real date regex (see
+// chunk0-4) rather than matching any name containing "2023"/"2024"/"2025".
+fn default_rules() -> RulesConfig {
+    let strs = |xs: &[&str]| xs.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    RulesConfig {
+        rules: vec![
+            CategoryRule {
+                category: "Sensitive".to_string(),
+                priority: 100,
+                extensions: vec![],
+                keywords: strs(&[
+                    "tax", "irs", "w2", "1099", "ssn", "social", "security",
+                    "bank", "account", "statement", "routing", "financial",
+                    "password", "credential", "key", "secret", "login", "auth",
+                    "medical", "health", "prescription", "doctor", "patient",
+                    "personal", "private", "confidential", "classified",
+                ]),
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Work Documents".to_string(),
+                priority: 50,
+                extensions: strs(&["pdf", "doc", "docx", "txt", "rtf", "odt"]),
+                keywords: strs(&[
+                    "meeting", "presentation", "report", "proposal", "contract",
+                    "client", "project", "deadline", "invoice", "budget",
+                    "company", "corporate", "business", "professional",
+                    "quarterly", "annual", "fiscal", "revenue", "salary",
+                ]),
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Personal Photos".to_string(),
+                priority: 50,
+                extensions: strs(&[
+                    "jpg", "jpeg", "png", "gif", "bmp", "tiff", "svg", "webp", "heic",
+                ]),
+                keywords: strs(&[
+                    "vacation", "holiday", "trip", "travel", "family",
+                    "birthday", "wedding", "anniversary", "graduation",
+                    "photo", "pic", "img", "selfie", "camera",
+                ]),
+                // Any embedded date token (2024-03-15, 20240315, IMG_20240315)
+                // marks a photo, replacing the old hardcoded year list.
+                patterns: strs(&[r"(19|20)\d{2}[-_]?\d{2}[-_]?\d{2}"]),
+            },
+            CategoryRule {
+                category: "Documents".to_string(),
+                priority: 10,
+                extensions: strs(&[
+                    "pdf", "doc", "docx", "txt", "rtf", "odt",
+                    "xls", "xlsx", "csv", "ods", "ppt", "pptx", "odp",
+                ]),
+                keywords: vec![],
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Images".to_string(),
+                priority: 10,
+                extensions: strs(&[
+                    "jpg", "jpeg", "png", "gif", "bmp", "tiff", "svg", "webp", "heic",
+                ]),
+                keywords: vec![],
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Videos".to_string(),
+                priority: 10,
+                extensions: strs(&["mp4", "avi", "mov", "wmv", "flv", "mkv", "webm", "m4v"]),
+                keywords: vec![],
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Audio".to_string(),
+                priority: 10,
+                extensions: strs(&["mp3", "wav", "flac", "aac", "ogg", "wma", "m4a"]),
+                keywords: vec![],
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Archives".to_string(),
+                priority: 10,
+                extensions: strs(&["zip", "rar", "7z", "tar", "gz", "bz2", "xz"]),
+                keywords: vec![],
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Code".to_string(),
+                priority: 10,
+                extensions: strs(&[
+                    "js", "ts", "jsx", "tsx", "py", "java", "cpp", "c", "h", "css", "html",
+                    "php", "rb", "go", "rs", "swift", "kt", "cs", "vb", "sql", "json", "xml",
+                    "yml", "yaml",
+                ]),
+                keywords: vec![],
+                patterns: vec![],
+            },
+            CategoryRule {
+                category: "Software".to_string(),
+                priority: 10,
+                extensions: strs(&["exe", "msi", "dmg", "pkg", "deb", "rpm", "appx", "app"]),
+                keywords: vec![],
+                patterns: vec![],
+            },
+        ],
     }
-    
-    // Categorize by extension and content
-    match extension.as_str() {
-        // Documents
-        "pdf" | "doc" | "docx" | "txt" | "rtf" | "odt" => {
-            if is_work_document(&file_name) {
-                "Work Documents".to_string()
-            } else {
-                "Documents".to_string()
+}
+
+// Path to the user's rule file under the platform config dir.
+fn rules_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("FileSense").join("rules.json"))
+}
+
+// A rule plus its compiled regexes, ready to match against files.
+struct CompiledRule {
+    category: String,
+    extensions: Vec<String>,
+    keywords: Vec<String>,
+    regexes: Vec<regex::Regex>,
+    // Whether the configured rule listed any keywords or patterns, even if
+    // every pattern later failed to compile.
+    name_constrained: bool,
+}
+
+// Runtime categorizer: rules sorted by descending priority with regexes
+// compiled once up front.
+struct Categorizer {
+    rules: Vec<CompiledRule>,
+}
+
+impl Categorizer {
+    // Build from a config, sorting by priority (highest first). A stable sort
+    // keeps same-priority rules in their declared order. Unparseable regexes
+    // are dropped with a warning rather than aborting the whole scan.
+    fn from_config(mut config: RulesConfig) -> Self {
+        config.rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        let rules = config
+            .rules
+            .into_iter()
+            .map(|rule| {
+                let name_constrained = !rule.keywords.is_empty() || !rule.patterns.is_empty();
+                let regexes = rule
+                    .patterns
+                    .iter()
+                    .filter_map(|pat| match regex::Regex::new(pat) {
+                        Ok(re) => Some(re),
+                        Err(e) => {
+                            println!("⚠️ Ignoring invalid rule pattern {:?}: {}", pat, e);
+                            None
+                        }
+                    })
+                    .collect();
+                CompiledRule {
+                    category: rule.category,
+                    extensions: rule.extensions.iter().map(|e| e.to_lowercase()).collect(),
+                    keywords: rule.keywords.iter().map(|k| k.to_lowercase()).collect(),
+                    regexes,
+                    name_constrained,
+                }
+            })
+            .collect();
+        Categorizer { rules }
+    }
+
+    // Load the user's rules, falling back to the built-in defaults when the
+    // file is absent or unreadable.
+    fn load() -> Self {
+        Self::from_config(load_rules_config())
+    }
+
+    // Categorize a file against the ordered rules, defaulting to "Other".
+    fn categorize(&self, file_info: &FileInfo) -> String {
+        let file_name = file_info.name.to_lowercase();
+        let extension = file_info.extension.to_lowercase();
+
+        for rule in &self.rules {
+            let ext_ok =
+                rule.extensions.is_empty() || rule.extensions.iter().any(|e| e == &extension);
+            if !ext_ok {
+                continue;
             }
-        },
-        
-        // Spreadsheets & Presentations
-        "xls" | "xlsx" | "csv" | "ods" | "ppt" | "pptx" | "odp" => "Documents".to_string(),
-        
-        // Images
-        "jpg" | "jpeg" | "png" | "gif" | "bmp" | "tiff" | "svg" | "webp" | "heic" => {
-            if is_personal_photo(&file_name) {
-                "Personal Photos".to_string()
+
+            let name_ok = if rule.name_constrained {
+                rule.keywords.iter().any(|k| file_name.contains(k))
+                    || rule.regexes.iter().any(|re| re.is_match(&file_name))
             } else {
-                "Images".to_string()
+                true
+            };
+
+            if name_ok {
+                return rule.category.clone();
+            }
+        }
+
+        "Other".to_string()
+    }
+}
+
+// Media categories whose files get routed into structured subfolders.
+const MEDIA_CATEGORIES: [&str; 3] = ["Images", "Personal Photos", "Videos"];
+
+// Extracts structure from media filenames so organized media lands in dated or
+// per-season subfolders instead of one flat bucket. Generalizes the old "20" +
+// year substring heuristic into real pattern extraction.
+struct MediaMatcher {
+    date: regex::Regex,
+    series: regex::Regex,
+}
+
+impl MediaMatcher {
+    fn new() -> Self {
+        MediaMatcher {
+            // 2024-03-15, 2024_03_15, 20240315, IMG_20240315, DSC20240315, ...
+            date: regex::Regex::new(r"(19|20)(\d{2})[-_]?(\d{2})[-_]?(\d{2})").unwrap(),
+            // S01E02, s1e2, 1x02 — episode markers for video series. The `NxNN`
+            // form requires non-digit boundaries so resolution tokens like
+            // `1920x1080` don't masquerade as "season 20".
+            series: regex::Regex::new(
+                r"(?i)(?:^|[^a-z0-9])s(\d{1,2})[ ._-]?e\d{1,2}|(?:^|[^0-9])(\d{1,2})x\d{2}(?:[^0-9]|$)",
+            )
+            .unwrap(),
+        }
+    }
+
+    // Relative subfolder (under the category dir) for this file, if one can be
+    // inferred. Dated media becomes `YYYY/YYYY-MM`; series episodes become
+    // `<Show>/Season NN` (keyed by the title before the episode token so shows
+    // don't collapse into one shared season). Series matching only applies to
+    // videos (`allow_series`) so stray tokens in photo names can't fabricate a
+    // season folder.
+    fn subpath(&self, file_name: &str, allow_series: bool) -> Option<PathBuf> {
+        if let Some(caps) = self.date.captures(file_name) {
+            let year = format!("{}{}", &caps[1], &caps[2]);
+            let month = &caps[3];
+            let day = &caps[4];
+            let month_n: u32 = month.parse().unwrap_or(0);
+            let day_n: u32 = day.parse().unwrap_or(0);
+            // Reject impossible dates so we don't mistake arbitrary digit runs.
+            if (1..=12).contains(&month_n) && (1..=31).contains(&day_n) {
+                return Some(PathBuf::from(&year).join(format!("{}-{}", year, month)));
+            }
+        }
+
+        if allow_series {
+            if let Some(caps) = self.series.captures(file_name) {
+                let season = caps
+                    .get(1)
+                    .or_else(|| caps.get(2))
+                    .map(|m| m.as_str())
+                    .unwrap_or("0");
+                let season_n: u32 = season.parse().unwrap_or(0);
+                // Ignore implausible season numbers.
+                if (1..=50).contains(&season_n) {
+                    let season_dir = format!("Season {:02}", season_n);
+                    // The filename segment before the episode token is the show
+                    // title; `get(0)` starts at the boundary char just before it.
+                    let token_start = caps.get(0).map(|m| m.start()).unwrap_or(0);
+                    return Some(match clean_show_title(&file_name[..token_start]) {
+                        Some(show) => PathBuf::from(show).join(season_dir),
+                        None => PathBuf::from(season_dir),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// Turn the raw filename prefix before an episode token into a tidy show-folder
+// name (separators to spaces, trimmed, sanitised), or `None` when empty.
+fn clean_show_title(raw: &str) -> Option<String> {
+    let title = raw.replace(['.', '_'], " ");
+    let title = title.trim_matches(|c: char| c == ' ' || c == '-');
+    if title.is_empty() {
+        None
+    } else {
+        Some(sanitise_file_name::sanitise(title))
+    }
+}
+
+// Read the rule config from disk, or hand back the defaults when it is missing
+// or cannot be parsed.
+fn load_rules_config() -> RulesConfig {
+    let path = match rules_config_path() {
+        Some(p) => p,
+        None => return default_rules(),
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => match serde_json::from_str::<RulesConfig>(&contents) {
+            Ok(config) => config,
+            Err(e) => {
+                println!("⚠️ Invalid {:?}, using defaults: {}", path, e);
+                default_rules()
             }
         },
-        
-        // Videos
-        "mp4" | "avi" | "mov" | "wmv" | "flv" | "mkv" | "webm" | "m4v" => "Videos".to_string(),
-        
-        // Audio
-        "mp3" | "wav" | "flac" | "aac" | "ogg" | "wma" | "m4a" => "Audio".to_string(),
-        
-        // Archives
-        "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz" => "Archives".to_string(),
-        
-        // Code files
-        "js" | "ts" | "jsx" | "tsx" | "py" | "java" | "cpp" | "c" | "h" | "css" | "html" | 
-        "php" | "rb" | "go" | "rs" | "swift" | "kt" | "cs" | "vb" | "sql" | "json" | "xml" | "yml" | "yaml" => {
-            "Code".to_string()
+        Err(_) => default_rules(),
+    }
+}
+
+// Access-control and traversal-filter settings, persisted as `access.json`.
+// `approved_roots` is a capability list: analyze/organize/duplicate calls are
+// rejected unless their path sits inside one of these. The gate fails closed —
+// an empty list denies every path until a root is approved. The remaining
+// fields keep scans fast and focused.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AccessConfig {
+    #[serde(default)]
+    approved_roots: Vec<String>,
+    #[serde(default)]
+    exclude_globs: Vec<String>,
+    #[serde(default)]
+    allowed_extensions: Vec<String>,
+    #[serde(default)]
+    excluded_extensions: Vec<String>,
+}
+
+// Defaults exclude the usual noise directories and impose no extension limits.
+// `approved_roots` ships empty, which denies everything (see `is_within_approved`)
+// until the user approves a root — `select_folder`/`add_approved_root` bootstrap
+// the list, so normal use grants access to exactly the folders the user picks.
+fn default_access_config() -> AccessConfig {
+    AccessConfig {
+        approved_roots: vec![],
+        exclude_globs: vec!["node_modules".to_string(), ".git".to_string()],
+        allowed_extensions: vec![],
+        excluded_extensions: vec![],
+    }
+}
+
+// Path to the access config under the platform config dir.
+fn access_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("FileSense").join("access.json"))
+}
+
+// Read the access config from disk, falling back to the defaults when absent
+// or unparseable.
+fn load_access_config() -> AccessConfig {
+    let path = match access_config_path() {
+        Some(p) => p,
+        None => return default_access_config(),
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => match serde_json::from_str::<AccessConfig>(&contents) {
+            Ok(config) => config,
+            Err(e) => {
+                println!("⚠️ Invalid {:?}, using defaults: {}", path, e);
+                default_access_config()
+            }
         },
-        
-        // Executables and installers
-        "exe" | "msi" | "dmg" | "pkg" | "deb" | "rpm" | "appx" | "app" => "Software".to_string(),
-        
-        // Default category
-        _ => "Other".to_string(),
-    }
-}
-
-// Check if file contains sensitive information
-fn is_sensitive_file(file_name: &str) -> bool {
-    let sensitive_keywords = [
-        "tax", "irs", "w2", "1099", "ssn", "social", "security",
-        "bank", "account", "statement", "routing", "financial",
-        "password", "credential", "key", "secret", "login", "auth",
-        "medical", "health", "prescription", "doctor", "patient",
-        "personal", "private", "confidential", "classified"
-    ];
-    
-    sensitive_keywords.iter().any(|&keyword| file_name.contains(keyword))
+        Err(_) => default_access_config(),
+    }
 }
 
-// Check if file is work-related
-fn is_work_document(file_name: &str) -> bool {
-    let work_keywords = [
-        "meeting", "presentation", "report", "proposal", "contract",
-        "client", "project", "deadline", "invoice", "budget",
-        "company", "corporate", "business", "professional",
-        "quarterly", "annual", "fiscal", "revenue", "salary"
-    ];
-    
-    work_keywords.iter().any(|&keyword| file_name.contains(keyword))
+// Write the access config, creating the config directory if needed.
+fn save_access_config(config: &AccessConfig) -> Result<(), String> {
+    let path = access_config_path().ok_or("Could not locate a config directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize access config: {}", e))?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {:?}: {}", path, e))
 }
 
-// Check if image is a personal photo
-fn is_personal_photo(file_name: &str) -> bool {
-    let personal_keywords = [
-        "vacation", "holiday", "trip", "travel", "family",
-        "birthday", "wedding", "anniversary", "graduation",
-        "photo", "pic", "img", "selfie", "camera"
-    ];
-    
-    // Check for date patterns
-    let has_date_pattern = file_name.contains("20") && 
-        (file_name.contains("2023") || file_name.contains("2024") || file_name.contains("2025"));
-    
-    personal_keywords.iter().any(|&keyword| file_name.contains(keyword)) || has_date_pattern
+// Is `path` inside one of the approved roots? The gate fails closed: an empty
+// list approves nothing, so every path is denied until the user explicitly adds
+// a root (via the `add_approved_root` command, or `select_folder`, which calls
+// `add_approved_root_path` when a folder is picked). "Unscoped" means "locked", not "unrestricted".
+fn is_within_approved(path: &Path, roots: &[String]) -> bool {
+    if roots.is_empty() {
+        return false;
+    }
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    roots.iter().any(|root| {
+        let root = fs::canonicalize(root).unwrap_or_else(|_| PathBuf::from(root));
+        target.starts_with(&root)
+    })
 }
 
-// Get file size safely
-fn get_file_size(path: &Path) -> u64 {
-    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+// Gate an operation on the approved-roots capability list.
+fn ensure_approved(path: &Path) -> Result<(), String> {
+    let config = load_access_config();
+    if is_within_approved(path, &config.approved_roots) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Access denied: {} is outside the approved folders",
+            path.display()
+        ))
+    }
+}
+
+// Compiled traversal filter: exclude globs plus allowed/excluded extension
+// sets, applied per entry during the tree walk.
+struct ScanFilter {
+    exclude: Vec<glob::Pattern>,
+    allowed_ext: Vec<String>,
+    excluded_ext: Vec<String>,
+}
+
+impl ScanFilter {
+    fn from_config(config: &AccessConfig) -> Self {
+        let exclude = config
+            .exclude_globs
+            .iter()
+            .filter_map(|g| match glob::Pattern::new(g) {
+                Ok(p) => Some(p),
+                Err(e) => {
+                    println!("⚠️ Ignoring invalid exclude glob {:?}: {}", g, e);
+                    None
+                }
+            })
+            .collect();
+        ScanFilter {
+            exclude,
+            allowed_ext: config
+                .allowed_extensions
+                .iter()
+                .map(|e| e.to_lowercase())
+                .collect(),
+            excluded_ext: config
+                .excluded_extensions
+                .iter()
+                .map(|e| e.to_lowercase())
+                .collect(),
+        }
+    }
+
+    // Does this path match an exclude glob, either as a whole path or by its
+    // last component (so a bare `node_modules` skips it at any depth)?
+    fn is_excluded_path(&self, path: &Path) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str());
+        self.exclude.iter().any(|pat| {
+            pat.matches_path(path) || name.map(|n| pat.matches(n)).unwrap_or(false)
+        })
+    }
+
+    // Is this extension permitted by the allowed/excluded sets? Exclusion wins,
+    // and a non-empty allow list restricts to exactly its members.
+    fn extension_allowed(&self, extension: &str) -> bool {
+        let ext = extension.to_lowercase();
+        if !self.excluded_ext.is_empty() && self.excluded_ext.contains(&ext) {
+            return false;
+        }
+        if !self.allowed_ext.is_empty() && !self.allowed_ext.contains(&ext) {
+            return false;
+        }
+        true
+    }
+}
+
+// Turn an io::Error on a path into a typed diagnostic for the UI.
+fn bad_from_io(path: &Path, err: &std::io::Error) -> BadMatch {
+    let p = path.to_string_lossy().to_string();
+    match err.raw_os_error() {
+        Some(code) => BadMatch::OsError { path: p, code },
+        None => BadMatch::Unreadable { path: p },
+    }
+}
+
+// Scan one directory: split its entries into child directories (to keep
+// walking) and files (to categorize), statting only the files we keep.
+// Entries rejected by `filter` (excluded globs, extension sets) are skipped.
+fn scan_directory(dir: &Path, filter: &ScanFilter) -> DirScan {
+    let mut scan = DirScan::default();
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            scan.bad.push(bad_from_io(dir, &e));
+            return scan;
+        }
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                scan.bad.push(bad_from_io(dir, &e));
+                continue;
+            }
+        };
+
+        let file_path = entry.path();
+
+        // Skip hidden files and folders at any depth.
+        if file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with('.'))
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        // Skip anything matching an exclude glob (e.g. node_modules, .git).
+        if filter.is_excluded_path(&file_path) {
+            continue;
+        }
+
+        let file_type = match entry.file_type() {
+            Ok(ft) => ft,
+            Err(e) => {
+                scan.bad.push(bad_from_io(&file_path, &e));
+                continue;
+            }
+        };
+
+        if file_type.is_dir() {
+            scan.subdirs.push(file_path);
+            continue;
+        }
+
+        if !file_type.is_file() {
+            scan.bad.push(BadMatch::BadType {
+                path: file_path.to_string_lossy().to_string(),
+            });
+            continue;
+        }
+
+        let file_name = match file_path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => {
+                scan.bad.push(BadMatch::Unreadable {
+                    path: file_path.to_string_lossy().to_string(),
+                });
+                continue;
+            }
+        };
+
+        // Honour the allowed/excluded extension sets before spending a stat.
+        let extension = get_file_extension(&file_path);
+        if !filter.extension_allowed(&extension) {
+            continue;
+        }
+
+        // Lazily stat only the files we are actually keeping.
+        let metadata = match fs::metadata(&file_path) {
+            Ok(m) => m,
+            Err(e) => {
+                scan.bad.push(bad_from_io(&file_path, &e));
+                continue;
+            }
+        };
+
+        let modified_date = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        scan.files.push(FileInfo {
+            name: file_name,
+            path: file_path.to_string_lossy().to_string(),
+            size: metadata.len(),
+            extension,
+            modified_date,
+        });
+    }
+
+    scan
 }
 
 // Get file extension safely
@@ -152,109 +692,222 @@ async fn select_folder() -> Result<String, String> {
     
     if std::path::Path::new(&test_path).exists() {
         println!("✅ Using test folder: {}", test_path);
+        // Picking a folder grants the app access to it.
+        if let Err(e) = add_approved_root_path(&test_path) {
+            println!("⚠️ Could not record approved root: {}", e);
+        }
         Ok(test_path)
     } else {
         Err("Test folder not found. Please check the path.".to_string())
     }
 }
 
+// Sensible default when the caller does not pin a thread count.
+fn default_thread_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
 #[tauri::command]
-async fn analyze_folder(folder_path: String) -> Result<FolderAnalysis, String> {
+async fn analyze_folder(
+    window: tauri::Window,
+    control: tauri::State<'_, ScanControl>,
+    folder_path: String,
+    thread_count: Option<usize>,
+) -> Result<FolderAnalysis, String> {
     println!("🔍 Starting analysis of folder: {}", folder_path);
-    
+
+    // Clear any leftover cancellation from a previous run.
+    control.cancel.store(false, Ordering::SeqCst);
+
     let path = Path::new(&folder_path);
-    
+
     if !path.exists() {
         return Err(format!("Folder does not exist: {}", folder_path));
     }
-    
+
     if !path.is_dir() {
         return Err(format!("Path is not a directory: {}", folder_path));
     }
-    
+
+    // Reject paths outside the user-approved roots, and honour the scan filter.
+    ensure_approved(path)?;
+    let filter = ScanFilter::from_config(&load_access_config());
+
     let mut categories: HashMap<String, Vec<FileInfo>> = HashMap::new();
+    let mut bad_paths: Vec<BadMatch> = Vec::new();
     let mut total_files = 0;
-    
+
     // Initialize categories
     let category_names = vec![
-        "Documents", "Images", "Videos", "Audio", "Archives", 
-        "Code", "Software", "Work Documents", "Personal Photos", 
+        "Documents", "Images", "Videos", "Audio", "Archives",
+        "Code", "Software", "Work Documents", "Personal Photos",
         "Sensitive", "Other"
     ];
-    
+
     for category in category_names {
         categories.insert(category.to_string(), Vec::new());
     }
-    
-    // Read directory contents
-    let entries = match fs::read_dir(path) {
-        Ok(entries) => entries,
-        Err(e) => return Err(format!("Failed to read directory: {}", e)),
-    };
-    
-    for entry in entries {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(e) => {
-                println!("⚠️ Error reading entry: {}", e);
-                continue;
-            }
-        };
-        
-        let file_path = entry.path();
-        
-        // Skip directories and hidden files
-        if file_path.is_dir() || 
-           file_path.file_name()
-               .and_then(|name| name.to_str())
-               .map(|name| name.starts_with('.'))
-               .unwrap_or(false) {
-            continue;
+
+    // Walk the tree with an explicit work list instead of recursion so deep
+    // trees can't blow the stack. Each round pops the pending directories and
+    // scans them in parallel, then feeds their children back onto the list.
+    let threads = thread_count.unwrap_or_else(default_thread_count).max(1);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| format!("Failed to build thread pool: {}", e))?;
+
+    // Load the (possibly user-edited) categorization rules once per scan.
+    let categorizer = Categorizer::load();
+
+    let mut pending: Vec<PathBuf> = vec![path.to_path_buf()];
+    let mut last_emit = Instant::now();
+
+    while !pending.is_empty() {
+        // Honour a cancellation request between batches and hand back whatever
+        // we have gathered so far as a partial analysis.
+        if control.cancel.load(Ordering::SeqCst) {
+            println!("🛑 Analysis cancelled after {} files", total_files);
+            break;
         }
-        
-        let file_name = file_path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-        
-        let file_info = FileInfo {
-            name: file_name,
-            path: file_path.to_string_lossy().to_string(),
-            size: get_file_size(&file_path),
-            extension: get_file_extension(&file_path),
-        };
-        
-        let category = categorize_file(&file_info);
-        
-        if let Some(category_files) = categories.get_mut(&category) {
-            category_files.push(file_info);
-            total_files += 1;
+
+        let batch = std::mem::take(&mut pending);
+
+        let scans: Vec<DirScan> =
+            pool.install(|| batch.par_iter().map(|dir| scan_directory(dir, &filter)).collect());
+
+        for scan in scans {
+            pending.extend(scan.subdirs);
+            bad_paths.extend(scan.bad);
+
+            for file_info in scan.files {
+                // Rules are user-editable and may name categories we didn't
+                // pre-seed, so create the bucket on demand rather than dropping
+                // the file.
+                let category = categorizer.categorize(&file_info);
+                categories.entry(category).or_default().push(file_info);
+                total_files += 1;
+
+                // Log progress for large folders
+                if total_files % 100 == 0 {
+                    println!("📊 Processed {} files...", total_files);
+                }
+            }
         }
-        
-        // Log progress for large folders
-        if total_files % 100 == 0 {
-            println!("📊 Processed {} files...", total_files);
+
+        // Throttle progress events so we don't flood the UI thread.
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            let _ = window.emit(
+                "analysis-progress",
+                ProgressData {
+                    current_stage: 1,
+                    max_stage: 1,
+                    files_checked: total_files,
+                },
+            );
+            last_emit = Instant::now();
         }
     }
-    
+
+    // Final progress tick so the bar reaches 100%.
+    let _ = window.emit(
+        "analysis-progress",
+        ProgressData {
+            current_stage: 1,
+            max_stage: 1,
+            files_checked: total_files,
+        },
+    );
+
     println!("✅ Analysis complete: {} files categorized", total_files);
-    
+    if !bad_paths.is_empty() {
+        println!("⚠️ {} path(s) could not be read", bad_paths.len());
+    }
+
     // Log category summary
     for (category, files) in &categories {
         if !files.is_empty() {
             println!("📁 {}: {} files", category, files.len());
         }
     }
-    
+
     Ok(FolderAnalysis {
         total_files,
         categories,
+        bad_paths,
     })
 }
 
+// Is this a cross-filesystem move error? `rename` can't cross devices. The
+// errno differs per platform, so match each symbolically: EXDEV (18) on Unix,
+// ERROR_NOT_SAME_DEVICE (17) on Windows. Matching both unconditionally would
+// misread a Unix EEXIST (17) as cross-device and silently copy+delete.
+#[cfg(unix)]
+fn is_cross_device(err: &std::io::Error) -> bool {
+    err.raw_os_error() == Some(18)
+}
+
+#[cfg(windows)]
+fn is_cross_device(err: &std::io::Error) -> bool {
+    err.raw_os_error() == Some(17)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_cross_device(_err: &std::io::Error) -> bool {
+    false
+}
+
+// Move a file, falling back to copy+delete when source and destination live on
+// different filesystems (a plain `rename` fails with a cross-device error).
+fn move_file(src: &Path, dest: &Path) -> std::io::Result<()> {
+    match fs::rename(src, dest) {
+        Ok(()) => Ok(()),
+        Err(e) if is_cross_device(&e) => {
+            fs::copy(src, dest)?;
+            fs::remove_file(src)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// Pick a destination under `dir` that won't clobber an existing file: sanitise
+// the name, then append " (1)", " (2)", ... before the extension on collision.
+fn collision_free_dest(dir: &Path, file_name: &str) -> PathBuf {
+    let sanitised = sanitise_file_name::sanitise(file_name);
+
+    let candidate = dir.join(&sanitised);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let as_path = Path::new(&sanitised);
+    let stem = as_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&sanitised);
+    let ext = as_path.extension().and_then(|e| e.to_str());
+
+    let mut n = 1;
+    loop {
+        let name = match ext {
+            Some(ext) => format!("{} ({}).{}", stem, n, ext),
+            None => format!("{} ({})", stem, n),
+        };
+        let candidate = dir.join(name);
+        if !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
 #[tauri::command]
-async fn organize_files(organization_plan: serde_json::Value) -> Result<serde_json::Value, String> {
+async fn organize_files(
+    window: tauri::Window,
+    organization_plan: serde_json::Value,
+) -> Result<serde_json::Value, String> {
     use std::fs;
     use std::path::Path;
 
@@ -262,12 +915,17 @@ async fn organize_files(organization_plan: serde_json::Value) -> Result<serde_js
         .and_then(|v| v.as_str())
         .ok_or("Missing 'target_root' in organization plan")?;
 
+    // Refuse to write outside the user-approved roots.
+    ensure_approved(Path::new(target_root))?;
+
     let categories = organization_plan.as_object()
         .ok_or("Organization plan is not an object")?;
 
     let mut moved_files = 0;
     let mut errors = Vec::new();
     let mut moves: Vec<FileMove> = Vec::new();
+    let mut last_emit = Instant::now();
+    let matcher = MediaMatcher::new();
 
     for (category, files) in categories {
         if category == "target_root" {
@@ -279,12 +937,9 @@ async fn organize_files(organization_plan: serde_json::Value) -> Result<serde_js
         };
 
         let category_dir = Path::new(target_root).join(category);
-        if !category_dir.exists() {
-            if let Err(e) = fs::create_dir_all(&category_dir) {
-                errors.push(format!("Failed to create directory {:?}: {}", category_dir, e));
-                continue;
-            }
-        }
+        let is_media = MEDIA_CATEGORIES.contains(&category.as_str());
+        // Series/season routing only makes sense for video files.
+        let allow_series = category == "Videos";
 
         for file in files {
             let src = match file.get("path").and_then(|v| v.as_str()) {
@@ -295,9 +950,32 @@ async fn organize_files(organization_plan: serde_json::Value) -> Result<serde_js
                 Some(n) => n,
                 None => continue,
             };
-            let dest = category_dir.join(file_name);
 
-            if let Err(e) = fs::rename(src, &dest) {
+            // Media files get routed into the nested subfolder the matcher
+            // proposes (e.g. `Personal Photos/2024/2024-03`); everything else
+            // lands directly under its category.
+            // Pass the original name (not lowercased) so the show title keeps
+            // its case; the matcher's regexes are digit- or case-insensitive.
+            let dest_dir = match is_media
+                .then(|| matcher.subpath(file_name, allow_series))
+                .flatten()
+            {
+                Some(sub) => category_dir.join(sub),
+                None => category_dir.clone(),
+            };
+
+            if !dest_dir.exists() {
+                if let Err(e) = fs::create_dir_all(&dest_dir) {
+                    errors.push(format!("Failed to create directory {:?}: {}", dest_dir, e));
+                    continue;
+                }
+            }
+
+            // De-duplicate the name so we never silently overwrite a file that
+            // already lives at the destination.
+            let dest = collision_free_dest(&dest_dir, file_name);
+
+            if let Err(e) = move_file(Path::new(src), &dest) {
                 errors.push(format!("Failed to move {}: {}", src, e));
             } else {
                 moves.push(FileMove {
@@ -306,9 +984,31 @@ async fn organize_files(organization_plan: serde_json::Value) -> Result<serde_js
                 });
                 moved_files += 1;
             }
+
+            // Throttled progress so the UI can track the move.
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                let _ = window.emit(
+                    "organize-progress",
+                    ProgressData {
+                        current_stage: 2,
+                        max_stage: 2,
+                        files_checked: moved_files,
+                    },
+                );
+                last_emit = Instant::now();
+            }
         }
     }
 
+    let _ = window.emit(
+        "organize-progress",
+        ProgressData {
+            current_stage: 2,
+            max_stage: 2,
+            files_checked: moved_files,
+        },
+    );
+
     if errors.is_empty() {
         Ok(serde_json::json!({
             "message": format!("✅ Organized {} files successfully!", moved_files),
@@ -339,19 +1039,20 @@ async fn undo_organize(moves: Vec<FileMove>) -> Result<String, String> {
     }
 
     for file_move in &moves {
-        if let Err(e) = fs::rename(&file_move.to, &file_move.from) {
+        if let Err(e) = move_file(Path::new(&file_move.to), Path::new(&file_move.from)) {
             errors.push(format!("Failed to move back {}: {}", file_move.to, e));
         } else {
             undone += 1;
         }
     }
 
-    // Remove empty folders
+    // Remove emptied folders. Send them to the trash rather than unlinking so
+    // nothing is irrecoverably destroyed if we got the emptiness check wrong.
     for folder in folders_to_check {
         if folder.exists() && folder.read_dir().map(|mut i| i.next().is_none()).unwrap_or(false) {
-            // Move to trash (cross-platform, but you may need to add a crate like 'trash')
-            // For now, just remove the directory:
-            let _ = fs::remove_dir(&folder);
+            if let Err(e) = trash::delete(&folder) {
+                errors.push(format!("Failed to trash empty folder {:?}: {}", folder, e));
+            }
         }
     }
 
@@ -366,15 +1067,330 @@ async fn undo_organize(moves: Vec<FileMove>) -> Result<String, String> {
     }
 }
 
+// Return the current categorization rules for the settings UI (the user's
+// file if present, otherwise the built-in defaults).
+#[tauri::command]
+fn get_rules() -> RulesConfig {
+    load_rules_config()
+}
+
+// Persist an edited rule set to the config file, creating the directory if
+// needed.
+#[tauri::command]
+fn save_rules(config: RulesConfig) -> Result<(), String> {
+    let path = rules_config_path().ok_or("Could not locate a config directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize rules: {}", e))?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(())
+}
+
+// Signal an in-flight analysis to stop; it returns a partial result.
+#[tauri::command]
+fn cancel_scan(control: tauri::State<'_, ScanControl>) {
+    println!("🛑 Cancellation requested");
+    control.cancel.store(true, Ordering::SeqCst);
+}
+
+// Add a root to the capability list (idempotent), returning the updated config.
+fn add_approved_root_path(path: &str) -> Result<AccessConfig, String> {
+    let mut config = load_access_config();
+    if !config.approved_roots.iter().any(|r| r == path) {
+        config.approved_roots.push(path.to_string());
+    }
+    save_access_config(&config)?;
+    Ok(config)
+}
+
+// Return the access config (approved roots + filters) for the settings UI.
+#[tauri::command]
+fn get_access_config() -> AccessConfig {
+    load_access_config()
+}
+
+// Persist the whole access config from the settings UI.
+#[tauri::command]
+fn set_access_config(config: AccessConfig) -> Result<(), String> {
+    save_access_config(&config)
+}
+
+// Convenience command to approve one more root folder.
+#[tauri::command]
+fn add_approved_root(path: String) -> Result<AccessConfig, String> {
+    add_approved_root_path(&path)
+}
+
+// A set of byte-identical files found within a scanned folder.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct DuplicateCluster {
+    size: u64,
+    files: Vec<FileInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DuplicateReport {
+    clusters: Vec<DuplicateCluster>,
+}
+
+// How the caller wants redundant copies resolved when deleting duplicates.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum DeleteMethod {
+    // Keep the most recently modified copy, trash the rest.
+    KeepNewest,
+    // Keep the oldest copy, trash the rest.
+    KeepOldest,
+    // Trash every file in the supplied clusters (caller has already curated).
+    Manual,
+}
+
+// Gather every file under `root`, reusing the same parallel tree walk as the
+// analysis pass (without the categorization/progress bookkeeping).
+fn walk_files(root: &Path, threads: usize, filter: &ScanFilter) -> Vec<FileInfo> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.max(1))
+        .build()
+        .ok();
+
+    let mut files = Vec::new();
+    let mut pending: Vec<PathBuf> = vec![root.to_path_buf()];
+
+    while !pending.is_empty() {
+        let batch = std::mem::take(&mut pending);
+        let scans: Vec<DirScan> = match &pool {
+            Some(p) => p.install(|| batch.par_iter().map(|d| scan_directory(d, filter)).collect()),
+            None => batch.iter().map(|d| scan_directory(d, filter)).collect(),
+        };
+        for scan in scans {
+            pending.extend(scan.subdirs);
+            files.extend(scan.files);
+        }
+    }
+
+    files
+}
+
+// Hash the first `max_bytes` of a file — a cheap fingerprint used to rule out
+// non-duplicates before paying for a full read.
+fn hash_prefix(path: &str, max_bytes: usize) -> std::io::Result<u64> {
+    use std::hash::Hasher;
+    use std::io::Read;
+
+    let mut file = fs::File::open(path)?;
+    let mut buf = vec![0u8; max_bytes];
+    // `read` may return short without hitting EOF, so fill the buffer in a loop
+    // until we reach `max_bytes` or a genuine EOF. Otherwise two identical files
+    // could fingerprint different prefixes and be missed as duplicates.
+    let mut filled = 0;
+    while filled < max_bytes {
+        let n = file.read(&mut buf[filled..])?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    hasher.write(&buf[..filled]);
+    Ok(hasher.finish())
+}
+
+// Hash a file's full contents to confirm a match once sizes and prefixes agree.
+fn hash_full(path: &str) -> std::io::Result<u64> {
+    use std::hash::Hasher;
+    use std::io::Read;
+
+    let mut file = fs::File::open(path)?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut buf = [0u8; 16 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.write(&buf[..n]);
+    }
+    Ok(hasher.finish())
+}
+
+// Confirm duplicates within a single same-size group: fingerprint by prefix
+// first, then full-hash only the files whose prefixes collide.
+fn confirm_size_group(size: u64, group: &[FileInfo]) -> Vec<DuplicateCluster> {
+    const PREFIX_BYTES: usize = 8 * 1024;
+
+    let mut by_prefix: HashMap<u64, Vec<FileInfo>> = HashMap::new();
+    for file in group {
+        if let Ok(hash) = hash_prefix(&file.path, PREFIX_BYTES) {
+            by_prefix.entry(hash).or_default().push(file.clone());
+        }
+    }
+
+    let mut clusters = Vec::new();
+    for (_, prefix_group) in by_prefix {
+        if prefix_group.len() < 2 {
+            continue;
+        }
+
+        let mut by_full: HashMap<u64, Vec<FileInfo>> = HashMap::new();
+        for file in prefix_group {
+            if let Ok(hash) = hash_full(&file.path) {
+                by_full.entry(hash).or_default().push(file);
+            }
+        }
+
+        for (_, dups) in by_full {
+            if dups.len() >= 2 {
+                clusters.push(DuplicateCluster { size, files: dups });
+            }
+        }
+    }
+
+    clusters
+}
+
+// Pre-filter by size, then confirm each candidate group in parallel.
+fn detect_duplicates(files: Vec<FileInfo>, threads: usize) -> Vec<DuplicateCluster> {
+    let mut by_size: HashMap<u64, Vec<FileInfo>> = HashMap::new();
+    for file in files {
+        // Empty files are all trivially "equal"; skip them.
+        if file.size > 0 {
+            by_size.entry(file.size).or_default().push(file);
+        }
+    }
+
+    let candidates: Vec<(u64, Vec<FileInfo>)> = by_size
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .collect();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.max(1))
+        .build()
+        .ok();
+
+    match pool {
+        Some(p) => p.install(|| {
+            candidates
+                .par_iter()
+                .flat_map(|(size, group)| confirm_size_group(*size, group))
+                .collect()
+        }),
+        None => candidates
+            .iter()
+            .flat_map(|(size, group)| confirm_size_group(*size, group))
+            .collect(),
+    }
+}
+
+#[tauri::command]
+async fn find_duplicates(
+    folder_path: String,
+    thread_count: Option<usize>,
+) -> Result<DuplicateReport, String> {
+    println!("🔁 Scanning for duplicates in: {}", folder_path);
+
+    let path = Path::new(&folder_path);
+    if !path.is_dir() {
+        return Err(format!("Path is not a directory: {}", folder_path));
+    }
+
+    ensure_approved(path)?;
+    let filter = ScanFilter::from_config(&load_access_config());
+
+    let threads = thread_count.unwrap_or_else(default_thread_count).max(1);
+    let files = walk_files(path, threads, &filter);
+    let clusters = detect_duplicates(files, threads);
+
+    println!("🔁 Found {} duplicate cluster(s)", clusters.len());
+    Ok(DuplicateReport { clusters })
+}
+
+#[tauri::command]
+async fn delete_duplicates(
+    clusters: Vec<DuplicateCluster>,
+    method: DeleteMethod,
+) -> Result<serde_json::Value, String> {
+    let mut deleted = 0;
+    let mut errors = Vec::new();
+
+    // Never trash files outside the approved roots, even if the caller hands us
+    // paths pointing elsewhere.
+    let roots = load_access_config().approved_roots;
+
+    for cluster in &clusters {
+        // Decide which copies are redundant based on the chosen policy.
+        let to_delete: Vec<&FileInfo> = match method {
+            DeleteMethod::Manual => cluster.files.iter().collect(),
+            DeleteMethod::KeepNewest | DeleteMethod::KeepOldest => {
+                if cluster.files.len() < 2 {
+                    continue;
+                }
+                let keep = match method {
+                    DeleteMethod::KeepNewest => {
+                        cluster.files.iter().max_by_key(|f| f.modified_date)
+                    }
+                    _ => cluster.files.iter().min_by_key(|f| f.modified_date),
+                };
+                let keep_path = keep.map(|f| &f.path);
+                cluster
+                    .files
+                    .iter()
+                    .filter(|f| Some(&f.path) != keep_path)
+                    .collect()
+            }
+        };
+
+        for file in to_delete {
+            if !is_within_approved(Path::new(&file.path), &roots) {
+                errors.push(format!(
+                    "Access denied: {} is outside the approved folders",
+                    file.path
+                ));
+                continue;
+            }
+            if let Err(e) = trash::delete(&file.path) {
+                errors.push(format!("Failed to trash {}: {}", file.path, e));
+            } else {
+                deleted += 1;
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(serde_json::json!({
+            "message": format!("🗑️ Sent {} duplicate(s) to trash", deleted),
+            "deleted": deleted
+        }))
+    } else {
+        Err(format!(
+            "Trashed {} duplicate(s), but some errors occurred:\n{}",
+            deleted,
+            errors.join("\n")
+        ))
+    }
+}
+
 fn main() {
     println!("🚀 Starting FileSense...");
-    
+
     tauri::Builder::default()
+        .manage(ScanControl::default())
         .invoke_handler(tauri::generate_handler![
             select_folder,
             analyze_folder,
             organize_files,
-            undo_organize
+            undo_organize,
+            cancel_scan,
+            get_rules,
+            save_rules,
+            find_duplicates,
+            delete_duplicates,
+            get_access_config,
+            set_access_config,
+            add_approved_root
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");